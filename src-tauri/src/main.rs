@@ -90,7 +90,25 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .invoke_handler(tauri::generate_handler![open_url, open_folder, reveal_in_finder, path_kind, save_text_file, read_text_file])
+        .invoke_handler(tauri::generate_handler![
+            open_url,
+            open_folder,
+            reveal_in_finder,
+            path_kind,
+            save_text_file,
+            read_text_file,
+            graph_device_code_start,
+            graph_device_code_poll,
+            graph_token_refresh,
+            graph_list_events,
+            graph_create_event,
+            secret_set,
+            secret_get,
+            secret_remove,
+            fetch_open_graph,
+            check_paths,
+            resolve_moved_file
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -104,6 +122,133 @@ fn path_kind(path: String) -> Result<String, String> {
     }
 }
 
+// Batched, richer version of `path_kind` for validating a whole project's
+// linked attachments at once.
+#[derive(serde::Serialize, Debug, Clone)]
+struct PathStatus {
+    path: String,
+    exists: bool,
+    kind: String,
+    size: Option<u64>,
+    modified: Option<i64>,
+    hash: Option<String>,
+}
+
+// Files larger than this aren't hashed, since check_paths runs over a whole
+// project's attachments and must stay cheap.
+const CHECK_PATHS_HASH_MAX_BYTES: u64 = 20 * 1024 * 1024;
+
+fn hash_file(path: &std::path::Path) -> Option<String> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = file.read(&mut buf).ok()?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+fn path_status(path: &str) -> PathStatus {
+    let meta = match std::fs::metadata(path) {
+        Ok(meta) => meta,
+        Err(_) => {
+            return PathStatus {
+                path: path.to_string(),
+                exists: false,
+                kind: "missing".to_string(),
+                size: None,
+                modified: None,
+                hash: None,
+            }
+        }
+    };
+    let kind = if meta.is_dir() { "folder" } else { "file" };
+    let size = if meta.is_file() { Some(meta.len()) } else { None };
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+    let hash = if meta.is_file() && meta.len() <= CHECK_PATHS_HASH_MAX_BYTES {
+        hash_file(std::path::Path::new(path))
+    } else {
+        None
+    };
+    PathStatus { path: path.to_string(), exists: true, kind: kind.to_string(), size, modified, hash }
+}
+
+#[tauri::command]
+fn check_paths(paths: Vec<String>) -> Vec<PathStatus> {
+    paths.iter().map(|p| path_status(p)).collect()
+}
+
+fn search_dir(
+    dir: &std::path::Path,
+    file_name: &str,
+    expected_size: Option<u64>,
+    expected_hash: Option<&str>,
+) -> Option<String> {
+    let entries = std::fs::read_dir(dir).ok()?;
+    let mut subdirs = Vec::new();
+    for entry in entries.flatten() {
+        // Don't follow symlinks: a link that points back at an ancestor
+        // directory (common in synced folders like Dropbox/OneDrive) would
+        // otherwise send this recursion into an infinite loop.
+        let Ok(file_type) = entry.file_type() else { continue };
+        if file_type.is_symlink() {
+            continue;
+        }
+        let path = entry.path();
+        if file_type.is_dir() {
+            subdirs.push(path);
+            continue;
+        }
+        if path.file_name().and_then(|n| n.to_str()) != Some(file_name) {
+            continue;
+        }
+        let meta = match entry.metadata() {
+            Ok(meta) => meta,
+            Err(_) => continue,
+        };
+        if expected_size.is_some_and(|size| meta.len() != size) {
+            continue;
+        }
+        if let Some(expected_hash) = expected_hash {
+            if hash_file(&path).as_deref() != Some(expected_hash) {
+                continue;
+            }
+        }
+        return Some(path.to_string_lossy().into_owned());
+    }
+    subdirs.into_iter().find_map(|subdir| search_dir(&subdir, file_name, expected_size, expected_hash))
+}
+
+// When a linked attachment has gone missing, look for a same-named file with
+// a matching size/hash under `search_roots` and propose it as the new path.
+// The caller is expected to follow up with `reveal_in_finder` once the user
+// accepts the match.
+#[tauri::command]
+fn resolve_moved_file(
+    original_path: String,
+    search_roots: Vec<String>,
+    expected_size: Option<u64>,
+    expected_hash: Option<String>,
+) -> Result<Option<String>, String> {
+    let file_name = std::path::Path::new(&original_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("original_path has no file name")?;
+    Ok(search_roots
+        .iter()
+        .find_map(|root| search_dir(std::path::Path::new(root), file_name, expected_size, expected_hash.as_deref())))
+}
+
 #[tauri::command]
 async fn graph_device_code_start(client_id: String) -> Result<serde_json::Value, String> {
     let scopes = "offline_access openid profile email https://graph.microsoft.com/Calendars.ReadWrite https://graph.microsoft.com/User.Read";
@@ -134,10 +279,347 @@ struct GraphTokens {
     refresh_token: Option<String>,
     expires_in: Option<i64>,
     token_type: Option<String>,
+    #[serde(default)]
+    obtained_at: i64,
 }
 
-fn graph_tokens_path(app: &tauri::AppHandle) -> std::path::PathBuf {
+// Pre-keychain plaintext token file, kept around only so `load_graph_tokens`
+// can import and delete it on first run after upgrading.
+fn legacy_graph_tokens_path(app: &tauri::AppHandle) -> std::path::PathBuf {
     let dir = app.path().app_config_dir().unwrap_or(std::env::temp_dir());
     let _ = std::fs::create_dir_all(&dir);
     dir.join("graph_tokens.json")
 }
+
+fn graph_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+const SECRET_SERVICE: &str = "PhDPlanner";
+const GRAPH_TOKENS_ACCOUNT: &str = "graph_tokens";
+
+fn keyring_entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SECRET_SERVICE, account).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn secret_set(key: String, value: String) -> Result<(), String> {
+    keyring_entry(&key)?.set_password(&value).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn secret_get(key: String) -> Result<String, String> {
+    keyring_entry(&key)?.get_password().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn secret_remove(key: String) -> Result<(), String> {
+    keyring_entry(&key)?.delete_password().map_err(|e| e.to_string())
+}
+
+fn save_graph_tokens(_app: &tauri::AppHandle, tokens: &GraphTokens) -> Result<(), String> {
+    let json = serde_json::to_string(tokens).map_err(|e| e.to_string())?;
+    keyring_entry(GRAPH_TOKENS_ACCOUNT)?.set_password(&json).map_err(|e| e.to_string())
+}
+
+fn load_graph_tokens(app: &tauri::AppHandle) -> Result<GraphTokens, String> {
+    if let Ok(json) = keyring_entry(GRAPH_TOKENS_ACCOUNT)?.get_password() {
+        return serde_json::from_str(&json).map_err(|e| e.to_string());
+    }
+
+    // First run after the keychain migration: import the old plaintext file
+    // into the keychain, then remove it so the secret only lives in one place.
+    let legacy_path = legacy_graph_tokens_path(app);
+    let contents = std::fs::read_to_string(&legacy_path)
+        .map_err(|_| "not signed in to Microsoft Graph".to_string())?;
+    let tokens: GraphTokens = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+    save_graph_tokens(app, &tokens)?;
+    let _ = std::fs::remove_file(&legacy_path);
+    Ok(tokens)
+}
+
+fn graph_access_token(app: &tauri::AppHandle) -> Result<String, String> {
+    let tokens = load_graph_tokens(app)?;
+    if let Some(expires_in) = tokens.expires_in {
+        if graph_now() >= tokens.obtained_at + expires_in {
+            return Err("Graph access token expired, call graph_token_refresh".to_string());
+        }
+    }
+    Ok(tokens.access_token)
+}
+
+// Structured status for the frontend's device-code poll loop. `interval` is
+// always the number of seconds to wait before the next poll.
+#[derive(serde::Serialize, Debug, Clone)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum GraphPollStatus {
+    Pending { interval: i64 },
+    SlowDown { interval: i64 },
+    Success { tokens: GraphTokens },
+    Failed { reason: String },
+}
+
+#[tauri::command]
+async fn graph_device_code_poll(
+    app: tauri::AppHandle,
+    client_id: String,
+    device_code: String,
+    interval: i64,
+) -> Result<GraphPollStatus, String> {
+    let body = [
+        ("client_id", client_id.as_str()),
+        ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+        ("device_code", device_code.as_str()),
+    ];
+    let resp = reqwest::Client::new()
+        .post("https://login.microsoftonline.com/organizations/oauth2/v2.0/token")
+        .form(&body)
+        .send().await.map_err(|e| e.to_string())?;
+    let json = resp.json::<serde_json::Value>().await.map_err(|e| e.to_string())?;
+
+    if let Some(error) = json.get("error").and_then(|v| v.as_str()) {
+        return Ok(match error {
+            "authorization_pending" => GraphPollStatus::Pending { interval },
+            "slow_down" => GraphPollStatus::SlowDown { interval: interval + 5 },
+            other => GraphPollStatus::Failed { reason: other.to_string() },
+        });
+    }
+
+    let mut tokens: GraphTokens = serde_json::from_value(json).map_err(|e| e.to_string())?;
+    tokens.obtained_at = graph_now();
+    save_graph_tokens(&app, &tokens)?;
+    Ok(GraphPollStatus::Success { tokens })
+}
+
+#[tauri::command]
+async fn graph_token_refresh(app: tauri::AppHandle, client_id: String) -> Result<GraphTokens, String> {
+    let stored = load_graph_tokens(&app)?;
+    let refresh_token = stored.refresh_token.clone().ok_or("no refresh token on file")?;
+    let body = [
+        ("client_id", client_id.as_str()),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token.as_str()),
+    ];
+    let resp = reqwest::Client::new()
+        .post("https://login.microsoftonline.com/organizations/oauth2/v2.0/token")
+        .form(&body)
+        .send().await.map_err(|e| e.to_string())?;
+    let json = resp.json::<serde_json::Value>().await.map_err(|e| e.to_string())?;
+    if let Some(error) = json.get("error").and_then(|v| v.as_str()) {
+        return Err(error.to_string());
+    }
+    let mut tokens: GraphTokens = serde_json::from_value(json).map_err(|e| e.to_string())?;
+    // The refresh_token grant isn't required to return a new refresh_token
+    // (RFC 6749 6); when it doesn't, keep using the one we already have
+    // instead of overwriting it with None and locking the user out later.
+    tokens.refresh_token = tokens.refresh_token.or(stored.refresh_token);
+    tokens.obtained_at = graph_now();
+    save_graph_tokens(&app, &tokens)?;
+    Ok(tokens)
+}
+
+#[tauri::command]
+async fn graph_list_events(
+    app: tauri::AppHandle,
+    start: String,
+    end: String,
+) -> Result<serde_json::Value, String> {
+    let token = graph_access_token(&app)?;
+    let resp = reqwest::Client::new()
+        .get("https://graph.microsoft.com/v1.0/me/calendarView")
+        .query(&[("startDateTime", &start), ("endDateTime", &end)])
+        .bearer_auth(token)
+        .send().await.map_err(|e| e.to_string())?;
+    graph_json_or_err(resp).await
+}
+
+#[tauri::command]
+async fn graph_create_event(
+    app: tauri::AppHandle,
+    subject: String,
+    body: String,
+    start: String,
+    end: String,
+) -> Result<serde_json::Value, String> {
+    let token = graph_access_token(&app)?;
+    let payload = serde_json::json!({
+        "subject": subject,
+        "body": { "contentType": "HTML", "content": body },
+        "start": { "dateTime": start, "timeZone": "UTC" },
+        "end": { "dateTime": end, "timeZone": "UTC" },
+    });
+    let resp = reqwest::Client::new()
+        .post("https://graph.microsoft.com/v1.0/me/events")
+        .bearer_auth(token)
+        .json(&payload)
+        .send().await.map_err(|e| e.to_string())?;
+    graph_json_or_err(resp).await
+}
+
+// Microsoft Graph returns a `{"error": {"code": ..., "message": ...}}` body
+// (a different shape from the OAuth endpoints' `{"error": "..."}`) on 4xx/5xx
+// responses, so a bad/expired token must be caught here before the caller
+// treats the body as a successful payload.
+async fn graph_json_or_err(resp: reqwest::Response) -> Result<serde_json::Value, String> {
+    let status = resp.status();
+    let json = resp.json::<serde_json::Value>().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        let message = json
+            .get("error")
+            .and_then(|e| e.get("message"))
+            .and_then(|m| m.as_str())
+            .map(|m| m.to_string())
+            .unwrap_or_else(|| json.to_string());
+        return Err(format!("Graph request failed ({status}): {message}"));
+    }
+    Ok(json)
+}
+
+// Rich preview for a linked URL (paper, journal, project page) so the UI can
+// show something better than a bare link, falling back to it on any failure.
+#[derive(serde::Serialize, Debug, Clone)]
+struct LinkPreview {
+    title: Option<String>,
+    description: Option<String>,
+    image: Option<String>,
+    domain: Option<String>,
+}
+
+const LINK_PREVIEW_MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+// Blocks loopback/link-local/private targets so a stored "paper link" can't
+// be used to probe the local network or cloud metadata endpoints.
+fn is_disallowed_ip(ip: std::net::IpAddr) -> bool {
+    use std::net::IpAddr;
+    match ip {
+        IpAddr::V4(v4) => {
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+        }
+        IpAddr::V6(v6) => {
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || (v6.segments()[0] & 0xfe00) == 0xfc00 // unique local fc00::/7
+                || (v6.segments()[0] & 0xffc0) == 0xfe80 // link-local fe80::/10
+                || v6.to_ipv4_mapped().is_some_and(|v4| is_disallowed_ip(IpAddr::V4(v4)))
+        }
+    }
+}
+
+// Resolves `parsed`'s host, rejecting it outright if any address is
+// loopback/private/link-local. Returns every *allowed* address so the caller
+// can pin the connection to one of them — re-resolving the host later (as a
+// plain DNS-based check-then-connect would) is vulnerable to DNS rebinding,
+// where an attacker's nameserver returns a public IP for this lookup and a
+// private one for the connection reqwest would otherwise perform itself.
+async fn ensure_fetchable(parsed: &url::Url) -> Result<Vec<std::net::SocketAddr>, String> {
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err("only http/https links can be previewed".to_string());
+    }
+    let host = parsed.host_str().ok_or("url has no host")?;
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    let addrs: Vec<std::net::SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| e.to_string())?
+        .collect();
+    if addrs.is_empty() {
+        return Err("url did not resolve to any address".to_string());
+    }
+    for addr in &addrs {
+        if is_disallowed_ip(addr.ip()) {
+            return Err("refusing to fetch a loopback/private/link-local address".to_string());
+        }
+    }
+    Ok(addrs)
+}
+
+#[tauri::command]
+async fn fetch_open_graph(url: String) -> Result<LinkPreview, String> {
+    let parsed = url::Url::parse(&url).map_err(|e| e.to_string())?;
+    let addrs = ensure_fetchable(&parsed).await?;
+    let host = parsed.host_str().ok_or("url has no host")?.to_string();
+
+    let mut builder = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(8))
+        .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) PhDPlanner/1.0")
+        // Redirects are not re-validated against the private-IP checks above,
+        // so follow none rather than risk a redirect to an internal host.
+        .redirect(reqwest::redirect::Policy::none());
+    // Pin this client's connections for `host` to the address(es) we just
+    // validated, instead of letting reqwest re-resolve DNS when it connects.
+    for addr in &addrs {
+        builder = builder.resolve(&host, *addr);
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+
+    let resp = client.get(parsed.clone()).send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("request failed with status {}", resp.status()));
+    }
+    if let Some(len) = resp.content_length() {
+        if len > LINK_PREVIEW_MAX_BODY_BYTES as u64 {
+            return Err("page is too large to preview".to_string());
+        }
+    }
+
+    use futures_util::StreamExt;
+    let mut body = Vec::new();
+    let mut stream = resp.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| e.to_string())?;
+        body.extend_from_slice(&chunk);
+        if body.len() > LINK_PREVIEW_MAX_BODY_BYTES {
+            body.truncate(LINK_PREVIEW_MAX_BODY_BYTES);
+            break;
+        }
+    }
+    let html = String::from_utf8_lossy(&body);
+
+    let document = scraper::Html::parse_document(&html);
+    let meta_sel = scraper::Selector::parse("meta").map_err(|e| e.to_string())?;
+    let title_sel = scraper::Selector::parse("title").map_err(|e| e.to_string())?;
+
+    let mut og_title = None;
+    let mut og_description = None;
+    let mut og_image = None;
+    let mut og_site_name = None;
+    let mut meta_description = None;
+
+    for el in document.select(&meta_sel) {
+        let property = el.value().attr("property").or_else(|| el.value().attr("name"));
+        let content = el.value().attr("content");
+        let (Some(property), Some(content)) = (property, content) else { continue };
+        match property {
+            "og:title" => og_title = Some(content.to_string()),
+            "og:description" => og_description = Some(content.to_string()),
+            "og:image" => og_image = Some(content.to_string()),
+            "og:site_name" => og_site_name = Some(content.to_string()),
+            "description" => meta_description = Some(content.to_string()),
+            _ => {}
+        }
+    }
+
+    let fallback_title = document
+        .select(&title_sel)
+        .next()
+        .map(|el| el.text().collect::<String>().trim().to_string());
+
+    let image = og_image.map(|src| match parsed.join(&src) {
+        Ok(resolved) => resolved.to_string(),
+        Err(_) => src,
+    });
+
+    Ok(LinkPreview {
+        title: og_title.or(fallback_title),
+        description: og_description.or(meta_description),
+        image,
+        domain: og_site_name.or_else(|| parsed.host_str().map(|h| h.to_string())),
+    })
+}